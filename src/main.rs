@@ -1,4 +1,4 @@
-use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*, window::PrimaryWindow};
 use rand::Rng;
 
 const TICK_INTERVAL: f32 = 0.15;
@@ -6,7 +6,6 @@ const GAME_WIDTH: i64 = 12;
 const GAME_HEIGHT: i64 = 12;
 const BLOCK_SIZE: i64 = 32;
 const GAP_SIZE: i64 = 2;
-const FOOD_SIZE: i64 = 24;
 const FOOD_INTERVAL: f32 = 1.00;
 const FOOD_MAX_COUNT: usize = 4;
 
@@ -14,12 +13,40 @@ const SNAKE_COLOR: Color = Color::rgb(169.0 / 255.0, 224.0 / 255.0, 0.0 / 255.0)
 const FOOD_COLOR: Color = Color::rgb(224.0 / 255.0, 45.0 / 255.0, 0.0 / 255.0);
 const BG_COLOR: Color = Color::rgb(100.0 / 255.0, 157.0 / 255.0, 0.0 / 255.0);
 
+#[derive(States, Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+enum GameState {
+    #[default]
+    Running,
+    GameOver,
+}
+
 #[derive(Resource)]
 struct TickTimer(Timer);
 
 #[derive(Resource)]
 struct FoodTimer(Timer);
 
+#[derive(Resource, Default)]
+struct SnakeSegments(Vec<Entity>);
+
+#[derive(Resource, Default)]
+struct LastTailPosition(Option<Pos>);
+
+#[derive(Resource, Default)]
+struct Score {
+    current: u64,
+    high: u64,
+}
+
+/// How the head behaves when a move would leave the arena: `Wrap` teleports it
+/// to the opposite edge (toroidal), `Death` treats the wall as a loss.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default)]
+enum WallMode {
+    #[default]
+    Wrap,
+    Death,
+}
+
 #[derive(Clone, PartialEq)]
 enum Dir {
     Right,
@@ -59,18 +86,15 @@ impl Pos {
             Dir::Left => self.x -= 1,
             Dir::Down => self.y += 1,
         }
-        if self.x < 0 {
-            self.x = GAME_WIDTH - 1;
-        }
-        if self.x > GAME_WIDTH - 1 {
-            self.x = 0;
-        }
-        if self.y < 0 {
-            self.y = GAME_HEIGHT - 1;
-        }
-        if self.y > GAME_HEIGHT - 1 {
-            self.y = 0;
-        }
+    }
+
+    fn out_of_bounds(&self) -> bool {
+        self.x < 0 || self.x >= GAME_WIDTH || self.y < 0 || self.y >= GAME_HEIGHT
+    }
+
+    fn wrap(&mut self) {
+        self.x = self.x.rem_euclid(GAME_WIDTH);
+        self.y = self.y.rem_euclid(GAME_HEIGHT);
     }
 
     fn move_to(&mut self, target: &Pos) {
@@ -79,34 +103,35 @@ impl Pos {
     }
 }
 
+/// A sprite's footprint as a fraction of a single grid tile, kept separate from
+/// its logical [`Pos`] so the arena can rescale when the window is resized.
+#[derive(Component, Clone, Copy)]
+struct Size {
+    width: f32,
+    height: f32,
+}
+
+impl Size {
+    fn square(x: f32) -> Self {
+        Self {
+            width: x,
+            height: x,
+        }
+    }
+}
+
 struct Spr {}
 
 impl Spr {
-    fn new(size: i64, color: Color) -> SpriteBundle {
+    fn new(color: Color) -> SpriteBundle {
         SpriteBundle {
             sprite: Sprite {
                 color,
-                custom_size: Some(Vec2::new(size as f32, size as f32)),
-                anchor: bevy::sprite::Anchor::TopLeft,
                 ..Default::default()
             },
-            visibility: Visibility::Hidden,
             ..Default::default()
         }
     }
-
-    fn translate(pos: &Pos, sprite: &Sprite, transform: &mut Transform) {
-        transform.translation.x =
-            (pos.x * (BLOCK_SIZE + GAP_SIZE) + GAP_SIZE) as f32
-            - (GAME_WIDTH * (BLOCK_SIZE + GAP_SIZE) + GAP_SIZE) as f32 / 2.0;
-        transform.translation.y =
-            (-pos.y * (BLOCK_SIZE + GAP_SIZE) + GAP_SIZE) as f32
-            + (GAME_HEIGHT * (BLOCK_SIZE + GAP_SIZE) - 3*GAP_SIZE) as f32 / 2.0;
-        if let Some(size) = sprite.custom_size {
-            transform.translation.x += (BLOCK_SIZE as f32 - size.x) / 2.0;
-            transform.translation.y -= (BLOCK_SIZE as f32 - size.y) / 2.0;
-        }
-    }
 }
 
 #[derive(Component)]
@@ -117,13 +142,24 @@ struct Snake {
 }
 
 #[derive(Component)]
-struct Body {
-    move_countdown: u64,
-}
+struct Body;
+
 #[derive(Component)]
 struct Food {}
 
-fn init(mut commands: Commands) {
+#[derive(Event)]
+struct GrowthEvent;
+
+#[derive(Event)]
+struct GameOverEvent;
+
+#[derive(Component)]
+struct GameOverText {}
+
+#[derive(Component)]
+struct ScoreText {}
+
+fn init(mut commands: Commands, mut segments: ResMut<SnakeSegments>) {
     commands.spawn(Camera2dBundle {
         camera_2d: Camera2d {
             clear_color: ClearColorConfig::Custom(BG_COLOR),
@@ -132,16 +168,89 @@ fn init(mut commands: Commands) {
     });
 
     commands.spawn((
-        Snake {
-            dir: Dir::Right,
-            next_dir: Dir::Right,
-            lenght: 0,
+        ScoreText {},
+        TextBundle::from_section(
+            "Score: 0  High: 0",
+            TextStyle {
+                font_size: 20.0,
+                color: SNAKE_COLOR,
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..Default::default()
+        }),
+    ));
+
+    spawn_snake(&mut commands, &mut segments);
+}
+
+fn spawn_snake(commands: &mut Commands, segments: &mut SnakeSegments) {
+    let head = commands
+        .spawn((
+            Snake {
+                dir: Dir::Right,
+                next_dir: Dir::Right,
+                lenght: 0,
+            },
+            Pos { x: 0, y: 0 },
+            Size::square(0.9),
+            Spr::new(SNAKE_COLOR),
+        ))
+        .id();
+    segments.0 = vec![head];
+}
+
+fn show_game_over(mut commands: Commands) {
+    commands.spawn((
+        GameOverText {},
+        Text2dBundle {
+            text: Text::from_section(
+                "Game Over — press Space",
+                TextStyle {
+                    font_size: 32.0,
+                    color: SNAKE_COLOR,
+                    ..Default::default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            ..Default::default()
         },
-        Pos { x: 0, y: 0 },
-        Spr::new(BLOCK_SIZE, SNAKE_COLOR),
     ));
 }
 
+fn restart(
+    mut commands: Commands,
+    input: Res<Input<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut segments: ResMut<SnakeSegments>,
+    mut last_tail: ResMut<LastTailPosition>,
+    mut score: ResMut<Score>,
+    snakes: Query<Entity, With<Snake>>,
+    bodies: Query<Entity, With<Body>>,
+    foods: Query<Entity, With<Food>>,
+    texts: Query<Entity, With<GameOverText>>,
+) {
+    if !input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for entity in snakes
+        .iter()
+        .chain(bodies.iter())
+        .chain(foods.iter())
+        .chain(texts.iter())
+    {
+        commands.entity(entity).despawn();
+    }
+    last_tail.0 = None;
+    score.current = 0;
+    spawn_snake(&mut commands, &mut segments);
+    next_state.set(GameState::Running);
+}
+
 fn main() {
     App::new()
     .add_plugins((
@@ -157,11 +266,28 @@ fn main() {
                 ..Default::default()
             }),
         ))
+        .add_state::<GameState>()
+        .init_resource::<SnakeSegments>()
+        .init_resource::<LastTailPosition>()
+        .init_resource::<Score>()
+        .insert_resource(WallMode::Wrap)
+        .add_event::<GrowthEvent>()
+        .add_event::<GameOverEvent>()
         .add_systems(Startup, init)
-        .add_systems(First, input)
-        .add_systems(PreUpdate, (tick, movement, translate_sprites))
-        .add_systems(Update, (spawn_food, eat_food))
-        .add_systems(PostUpdate, (log,))
+        .add_systems(First, input.run_if(in_state(GameState::Running)))
+        .add_systems(
+            PreUpdate,
+            (tick, movement).run_if(in_state(GameState::Running)),
+        )
+        .add_systems(
+            Update,
+            (spawn_food, (eat_food, snake_growth, game_over).chain())
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(Update, update_score_text)
+        .add_systems(Update, restart.run_if(in_state(GameState::GameOver)))
+        .add_systems(OnEnter(GameState::GameOver), show_game_over)
+        .add_systems(PostUpdate, (size_scaling, position_translation, log))
         .insert_resource(TickTimer(Timer::from_seconds(
             TICK_INTERVAL,
             TimerMode::Repeating,
@@ -199,40 +325,66 @@ fn input(mut snakes: Query<&mut Snake>, input: Res<Input<KeyCode>>) {
 }
 
 fn movement(
-    mut commands: Commands,
     timer: Res<TickTimer>,
-    mut snakes: Query<(&mut Snake, &mut Pos)>,
-    mut segments: Query<(Entity, &mut Body, &mut Pos), Without<Snake>>,
+    segments: Res<SnakeSegments>,
+    wall_mode: Res<WallMode>,
+    mut last_tail: ResMut<LastTailPosition>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+    mut score: ResMut<Score>,
+    mut snakes: Query<&mut Snake>,
+    mut positions: Query<&mut Pos>,
 ) {
-    if timer.0.just_finished() {
-        for (_, mut segment, mut pos) in segments.iter_mut() {
-            if segment.move_countdown > 0 {
-                segment.move_countdown -= 1;
-            } else {
-                for (snake, head) in snakes.iter_mut() {
-                    pos.move_to(&head);
-                    segment.move_countdown = snake.lenght - 1;
-                }
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    let head_entity = segments.0[0];
+
+    // Snapshot every segment's position before anything moves.
+    let snapshot: Vec<Pos> = segments
+        .0
+        .iter()
+        .map(|entity| positions.get(*entity).unwrap().clone())
+        .collect();
+
+    // Advance the head in its current direction.
+    let dir = {
+        let mut snake = snakes.get_mut(head_entity).unwrap();
+        snake.dir = snake.next_dir.clone();
+        snake.dir.clone()
+    };
+    positions.get_mut(head_entity).unwrap().move_dir(&dir);
+
+    // Resolve the arena edge according to the configured wall mode.
+    let mut dead = {
+        let mut head = positions.get_mut(head_entity).unwrap();
+        match *wall_mode {
+            WallMode::Wrap => {
+                head.wrap();
+                false
             }
+            WallMode::Death => head.out_of_bounds(),
         }
-        for (mut snake, mut head) in snakes.iter_mut() {
-            head.move_dir(&snake.next_dir);
-            snake.dir = snake.next_dir.clone();
-
-            let mut dead = false;
-            for (_, _, pos) in segments.iter_mut() {
-                if head.clone() == pos.clone() {
-                    dead = true;
-                    break;
-                }
-            }
-            if dead {
-                snake.lenght = 0;
-                for (entity, _, _) in segments.iter_mut() {
-                    commands.entity(entity).despawn();
-                }
-            }
+    };
+
+    // Each following segment takes the previous segment's old position.
+    for (entity, prev) in segments.0.iter().skip(1).zip(snapshot.iter()) {
+        positions.get_mut(*entity).unwrap().move_to(prev);
+    }
+    last_tail.0 = snapshot.last().cloned();
+
+    // Game over when the head runs into one of its own body segments.
+    let head = positions.get(head_entity).unwrap().clone();
+    dead |= segments
+        .0
+        .iter()
+        .skip(1)
+        .any(|entity| *positions.get(*entity).unwrap() == head);
+    if dead {
+        if score.current > score.high {
+            score.high = score.current;
         }
+        game_over_events.send(GameOverEvent);
     }
 }
 
@@ -255,7 +407,7 @@ fn spawn_food(
             }
             if !collision {
                 timer.0.tick(time.delta());
-                commands.spawn((Food {}, pos, Spr::new(FOOD_SIZE, FOOD_COLOR)));
+                commands.spawn((Food {}, pos, Size::square(0.8), Spr::new(FOOD_COLOR)));
                 break;
             }
         }
@@ -266,43 +418,87 @@ fn spawn_food(
 
 fn eat_food(
     mut commands: Commands,
-    mut snakes: Query<(&mut Snake, &mut Pos)>,
-    mut segments: Query<&mut Body>,
-    mut foods: Query<(Entity, &Food, &Pos), Without<Snake>>,
+    mut growth_events: EventWriter<GrowthEvent>,
+    mut score: ResMut<Score>,
+    mut snakes: Query<(&mut Snake, &Pos)>,
+    foods: Query<(Entity, &Pos), (With<Food>, Without<Snake>)>,
 ) {
     for (mut snake, head) in snakes.iter_mut() {
-        for (food, _, food_pos) in foods.iter_mut() {
-            if head.as_ref() == food_pos {
-                for mut segment in segments.iter_mut() {
-                    segment.move_countdown += 1
-                }
-
+        for (food, food_pos) in foods.iter() {
+            if head == food_pos {
                 commands.entity(food).despawn();
-                commands.spawn((
-                    Body {
-                        move_countdown: snake.lenght + 1,
-                    },
-                    head.clone(),
-                    Spr::new(BLOCK_SIZE, SNAKE_COLOR),
-                ));
                 snake.lenght += 1;
+                score.current += 1;
+                growth_events.send(GrowthEvent);
             }
         }
     }
 }
 
-fn translate_sprites(
-    timer: ResMut<TickTimer>,
-    mut query: Query<(&Pos, &Sprite, &mut Transform, &mut Visibility), With<Sprite>>,
+fn snake_growth(
+    mut commands: Commands,
+    mut growth_events: EventReader<GrowthEvent>,
+    mut segments: ResMut<SnakeSegments>,
+    last_tail: Res<LastTailPosition>,
 ) {
-    if timer.0.just_finished() {
-        for (pos, sprite, mut transform, mut visibility) in query.iter_mut() {
-            Spr::translate(pos, sprite, &mut transform);
-            *visibility = Visibility::Visible;
+    for _ in growth_events.iter() {
+        if let Some(tail_pos) = last_tail.0.clone() {
+            let body = commands
+                .spawn((Body, tail_pos, Size::square(0.9), Spr::new(SNAKE_COLOR)))
+                .id();
+            segments.0.push(body);
         }
     }
 }
 
+fn game_over(
+    mut game_over_events: EventReader<GameOverEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if game_over_events.iter().next().is_some() {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn update_score_text(score: Res<Score>, mut query: Query<&mut Text, With<ScoreText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Score: {}  High: {}", score.current, score.high);
+    }
+}
+
+fn size_scaling(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut query: Query<(&Size, &mut Sprite)>,
+) {
+    let window = windows.single();
+    for (size, mut sprite) in query.iter_mut() {
+        sprite.custom_size = Some(Vec2::new(
+            size.width / GAME_WIDTH as f32 * window.width(),
+            size.height / GAME_HEIGHT as f32 * window.height(),
+        ));
+    }
+}
+
+fn position_translation(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut query: Query<(&Pos, &mut Transform)>,
+) {
+    // Maps a logical tile coordinate onto pixel-space by treating the grid as a
+    // fraction of the window, so entities stay aligned when the window resizes.
+    fn convert(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
+        let tile_size = bound_window / bound_game;
+        pos / bound_game * bound_window - (bound_window / 2.0) + (tile_size / 2.0)
+    }
+    let window = windows.single();
+    for (pos, mut transform) in query.iter_mut() {
+        transform.translation = Vec3::new(
+            convert(pos.x as f32, window.width(), GAME_WIDTH as f32),
+            -convert(pos.y as f32, window.height(), GAME_HEIGHT as f32),
+            0.0,
+        );
+    }
+}
+
 fn log(timer: ResMut<TickTimer>, query: Query<&Pos, With<Snake>>) {
     if timer.0.just_finished() {
         for pos in query.iter() {